@@ -0,0 +1,277 @@
+use crate::prelude::*;
+use crate::multieq::MultiEq;
+use crate::uint::UInt32;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+/// The BLAKE2s message schedule: `SIGMA[r][i]` is the index into the 16-word
+/// message block used by round `r`'s `i`-th reference to a message word.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The BLAKE2s IV, `floor(2^32 * frac(sqrt(p_i)))` for the first eight primes.
+const IV: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+/// One mixing round of the `G` function, operating on four of the sixteen
+/// working words `v[a], v[b], v[c], v[d]` with message words `x, y`. Each of
+/// the four additions is routed through `multi_eq` so that a full round's
+/// worth of `G` invocations can be flushed as a handful of constraints
+/// instead of four per call.
+fn mixing_g<ConstraintF: PrimeField>(
+    multi_eq: &mut MultiEq<ConstraintF>,
+    v: &mut [UInt32<ConstraintF>],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: &UInt32<ConstraintF>,
+    y: &UInt32<ConstraintF>,
+) -> Result<(), SynthesisError> {
+    v[a] = UInt32::addmany(multi_eq, &[v[a].clone(), v[b].clone(), x.clone()])?;
+    v[d] = v[d].xor(&v[a])?.rotr(16);
+    v[c] = UInt32::addmany(multi_eq, &[v[c].clone(), v[d].clone()])?;
+    v[b] = v[b].xor(&v[c])?.rotr(12);
+
+    v[a] = UInt32::addmany(multi_eq, &[v[a].clone(), v[b].clone(), y.clone()])?;
+    v[d] = v[d].xor(&v[a])?.rotr(8);
+    v[c] = UInt32::addmany(multi_eq, &[v[c].clone(), v[d].clone()])?;
+    v[b] = v[b].xor(&v[c])?.rotr(7);
+
+    Ok(())
+}
+
+/// Runs the BLAKE2s compression function `F`, updating the eight-word state
+/// `h` in place from the sixteen-word message block `m`. `t` is the number of
+/// bytes fed into the hash so far (including this block), and `is_last_block`
+/// sets the finalization flag.
+fn compress<ConstraintF: PrimeField>(
+    h: &mut [UInt32<ConstraintF>],
+    m: &[UInt32<ConstraintF>],
+    t: u64,
+    is_last_block: bool,
+) -> Result<(), SynthesisError> {
+    assert_eq!(h.len(), 8);
+    assert_eq!(m.len(), 16);
+
+    let mut v = Vec::with_capacity(16);
+    v.extend_from_slice(h);
+    v.push(UInt32::constant(IV[0]));
+    v.push(UInt32::constant(IV[1]));
+    v.push(UInt32::constant(IV[2]));
+    v.push(UInt32::constant(IV[3]));
+    v.push(UInt32::constant(IV[4] ^ (t as u32)));
+    v.push(UInt32::constant(IV[5] ^ ((t >> 32) as u32)));
+    v.push(UInt32::constant(if is_last_block { !IV[6] } else { IV[6] }));
+    v.push(UInt32::constant(IV[7]));
+
+    {
+        let cs = h[0].cs().or(m[0].cs());
+        let mut multi_eq = MultiEq::new(cs);
+        for round in 0..10 {
+            let s = &SIGMA[round];
+            mixing_g(&mut multi_eq, &mut v, 0, 4, 8, 12, &m[s[0]], &m[s[1]])?;
+            mixing_g(&mut multi_eq, &mut v, 1, 5, 9, 13, &m[s[2]], &m[s[3]])?;
+            mixing_g(&mut multi_eq, &mut v, 2, 6, 10, 14, &m[s[4]], &m[s[5]])?;
+            mixing_g(&mut multi_eq, &mut v, 3, 7, 11, 15, &m[s[6]], &m[s[7]])?;
+            mixing_g(&mut multi_eq, &mut v, 0, 5, 10, 15, &m[s[8]], &m[s[9]])?;
+            mixing_g(&mut multi_eq, &mut v, 1, 6, 11, 12, &m[s[10]], &m[s[11]])?;
+            mixing_g(&mut multi_eq, &mut v, 2, 7, 8, 13, &m[s[12]], &m[s[13]])?;
+            mixing_g(&mut multi_eq, &mut v, 3, 4, 9, 14, &m[s[14]], &m[s[15]])?;
+        }
+        multi_eq.finish()?;
+    }
+
+    for i in 0..8 {
+        h[i] = h[i].xor(&v[i])?;
+        h[i] = h[i].xor(&v[i + 8])?;
+    }
+
+    Ok(())
+}
+
+/// Computes the BLAKE2s digest of `input`, returning 256 digest bits. `input`
+/// is split into 512-bit blocks and zero-padded to a block boundary; `salt`
+/// and `personalization` must each be exactly 8 bytes and are folded into the
+/// initial state the same way BLAKE2s's parameter block does (`salt` into
+/// `h[4]`/`h[5]`, `personalization` into `h[6]`/`h[7]`), matching the domain
+/// separation used by e.g. Zcash's Sapling circuits. Pass `&[0u8; 8]` for
+/// `salt` to match BLAKE2s's unsalted default.
+pub fn blake2s<ConstraintF: PrimeField>(
+    input: &[Boolean<ConstraintF>],
+    salt: &[u8],
+    personalization: &[u8],
+) -> Result<Vec<Boolean<ConstraintF>>, SynthesisError> {
+    assert_eq!(salt.len(), 8);
+    assert_eq!(personalization.len(), 8);
+    assert!(input.len() % 8 == 0);
+
+    let mut h = Vec::with_capacity(8);
+    h.push(UInt32::constant(IV[0] ^ 0x0101_0020));
+    h.push(UInt32::constant(IV[1]));
+    h.push(UInt32::constant(IV[2]));
+    h.push(UInt32::constant(IV[3]));
+    h.push(UInt32::constant(
+        IV[4] ^ u32::from_le_bytes([salt[0], salt[1], salt[2], salt[3]]),
+    ));
+    h.push(UInt32::constant(
+        IV[5] ^ u32::from_le_bytes([salt[4], salt[5], salt[6], salt[7]]),
+    ));
+    h.push(UInt32::constant(
+        IV[6] ^ u32::from_le_bytes([personalization[0], personalization[1], personalization[2], personalization[3]]),
+    ));
+    h.push(UInt32::constant(
+        IV[7] ^ u32::from_le_bytes([personalization[4], personalization[5], personalization[6], personalization[7]]),
+    ));
+
+    let mut blocks: Vec<Vec<Boolean<ConstraintF>>> = input.chunks(512).map(<[_]>::to_vec).collect();
+    if blocks.is_empty() {
+        blocks.push(Vec::new());
+    }
+    let last = blocks.len() - 1;
+    let input_byte_len = input.len() / 8;
+
+    for (i, block) in blocks.iter_mut().enumerate() {
+        block.resize(512, Boolean::constant(false));
+        let m: Vec<UInt32<ConstraintF>> = block.chunks(32).map(UInt32::from_bits_le).collect();
+
+        let is_last = i == last;
+        let t = if is_last {
+            input_byte_len as u64
+        } else {
+            (i as u64 + 1) * 64
+        };
+        compress(&mut h, &m, t, is_last)?;
+    }
+
+    let mut digest = Vec::with_capacity(256);
+    for word in &h {
+        digest.extend_from_slice(&word.to_bits_le()?);
+    }
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+    use ark_test_curves::bls12_381::Fr;
+
+    fn bits_from_bytes(bytes: &[u8]) -> Vec<Boolean<Fr>> {
+        bytes
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| Boolean::constant((byte >> i) & 1 == 1)))
+            .collect()
+    }
+
+    /// Like [`bits_from_bytes`], but allocates every bit as a witness on
+    /// `cs` instead of a constant, so the `MultiEq`-batched constraint path
+    /// in [`compress`] actually gets built.
+    fn bits_from_bytes_witness(cs: ConstraintSystemRef<Fr>, bytes: &[u8]) -> Vec<Boolean<Fr>> {
+        let mut bits = Vec::with_capacity(bytes.len() * 8);
+        for byte in bytes {
+            for i in 0..8 {
+                bits.push(Boolean::new_witness(cs.clone(), || Ok((byte >> i) & 1 == 1)).unwrap());
+            }
+        }
+        bits
+    }
+
+    fn bytes_from_bits(bits: &[Boolean<Fr>]) -> Vec<u8> {
+        bits.chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0u8, |acc, bit| (acc << 1) | (bit.value().unwrap() as u8))
+            })
+            .collect()
+    }
+
+    fn hex32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for i in 0..32 {
+            out[i] = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    // RFC 7693 BLAKE2s with the same 8-byte personalization this gadget
+    // folds into `h[6]`/`h[7]`, checked against `hashlib.blake2s(msg,
+    // digest_size=32, person=b"zcash-P0")` for the empty message, a short
+    // message, and a 43-byte message (all three fit in a single block).
+    #[test]
+    fn matches_personalized_known_answers() {
+        let personalization = b"zcash-P0";
+        let vectors: [(&[u8], [u8; 32]); 3] = [
+            (
+                b"",
+                hex32("fd9ae53786f6912d7998c032f4e6df469441359db5ac469649c2cffd6e922c3d"),
+            ),
+            (
+                b"abc",
+                hex32("eda652a16e631d1b66f36f76edadeb9706d2db98a6e0f7e967d77b0e834ace43"),
+            ),
+            (
+                b"hello world this is a longer message test12",
+                hex32("8f143ed85afea680816e4bade799c9a4ff645856bd3b97058e76b8a5bc89d546"),
+            ),
+        ];
+
+        for (msg, expected) in vectors {
+            let digest_bits = blake2s(&bits_from_bytes(msg), &[0u8; 8], personalization).unwrap();
+            assert_eq!(bytes_from_bits(&digest_bits), expected);
+        }
+    }
+
+    // Checked against `hashlib.blake2s(b"abc", digest_size=32,
+    // salt=b"mysalt12", person=b"zcash-P0")`, confirming a non-default salt
+    // actually changes the digest rather than being silently ignored.
+    #[test]
+    fn matches_salted_known_answer() {
+        let digest_bits = blake2s(&bits_from_bytes(b"abc"), b"mysalt12", b"zcash-P0").unwrap();
+        assert_eq!(
+            bytes_from_bits(&digest_bits),
+            hex32("36b5d7049c1f95c229fff9f4f8333bfc2c935becce4281a1cbfeefbcca03526e")
+        );
+    }
+
+    /// The KAT above only exercises `Boolean::constant` inputs, which take
+    /// `UInt32::addmany`'s all-constant fast path and never build a single
+    /// R1CS constraint. Here the message is witnessed on a real constraint
+    /// system instead, so the `MultiEq`-batched equality constraints are
+    /// actually emitted, and we check both that they're satisfied and that
+    /// the digest still matches the KAT.
+    #[test]
+    fn emitted_constraints_are_satisfied() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let input = bits_from_bytes_witness(cs.clone(), b"abc");
+
+        let digest_bits = blake2s(&input, &[0u8; 8], b"zcash-P0").unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(
+            bytes_from_bits(&digest_bits),
+            hex32("eda652a16e631d1b66f36f76edadeb9706d2db98a6e0f7e967d77b0e834ace43")
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError, Variable};
+use ark_std::mem;
+
+/// Scales every term of `lc` by `coeff`.
+pub(crate) fn scale_lc<ConstraintF: PrimeField>(
+    coeff: ConstraintF,
+    lc: &LinearCombination<ConstraintF>,
+) -> LinearCombination<ConstraintF> {
+    lc.0.iter().map(|(c, var)| (*c * coeff, *var)).collect()
+}
+
+/// Batches many small boolean-equality assertions into as few field-element
+/// equality constraints as possible.
+///
+/// This mirrors the `multieq` gadget from bellman's sapling circuit: rather
+/// than spending one constraint per `w`-bit value equality, each pending
+/// `lhs == rhs` pair is shifted by a running bit-offset `n` and accumulated
+/// into a pair of running linear combinations. Once `n` would exceed the
+/// field's usable capacity (`ConstraintF::MODULUS_BIT_SIZE - 1`), the
+/// accumulator is flushed as a single constraint before the new pair is
+/// folded in. Any remaining accumulation is flushed on `Drop`, so callers
+/// only need to call [`MultiEq::enforce_equal`].
+pub struct MultiEq<ConstraintF: PrimeField> {
+    cs: ConstraintSystemRef<ConstraintF>,
+    lhs: LinearCombination<ConstraintF>,
+    rhs: LinearCombination<ConstraintF>,
+    n: usize,
+}
+
+impl<ConstraintF: PrimeField> MultiEq<ConstraintF> {
+    /// Creates a fresh, empty accumulator over `cs`.
+    pub fn new(cs: ConstraintSystemRef<ConstraintF>) -> Self {
+        Self {
+            cs,
+            lhs: LinearCombination::zero(),
+            rhs: LinearCombination::zero(),
+            n: 0,
+        }
+    }
+
+    /// The number of bits that can be packed into a single field element
+    /// while leaving one bit of headroom against wraparound.
+    fn capacity() -> usize {
+        (ConstraintF::MODULUS_BIT_SIZE - 1) as usize
+    }
+
+    /// Enforces `lhs == rhs` for the current accumulation, then resets it.
+    /// A no-op if nothing is pending. Callers that need to observe a flush
+    /// failure (rather than relying on the best-effort flush in `Drop`)
+    /// should call [`MultiEq::finish`] once they're done enqueuing.
+    fn try_flush(&mut self) -> Result<(), SynthesisError> {
+        if self.n == 0 {
+            return Ok(());
+        }
+        let lhs = mem::replace(&mut self.lhs, LinearCombination::zero());
+        let rhs = mem::replace(&mut self.rhs, LinearCombination::zero());
+        self.cs
+            .enforce_constraint(lhs, LinearCombination::from((ConstraintF::one(), Variable::One)), rhs)?;
+        self.n = 0;
+        Ok(())
+    }
+
+    /// Queues the assertion that the `num_bits`-wide linear combinations
+    /// `lhs` and `rhs` are equal. The assertion is folded into the running
+    /// accumulator, scaled by `2^n` where `n` is the number of bits already
+    /// pending, and only becomes a real constraint once the accumulator is
+    /// flushed (on overflow, or when `self` is dropped or `finish`ed).
+    pub fn enforce_equal(
+        &mut self,
+        num_bits: usize,
+        lhs: &LinearCombination<ConstraintF>,
+        rhs: &LinearCombination<ConstraintF>,
+    ) -> Result<(), SynthesisError> {
+        if self.n + num_bits > Self::capacity() {
+            self.try_flush()?;
+        }
+
+        let coeff = ConstraintF::from(2u64).pow([self.n as u64]);
+        self.lhs = &self.lhs + &scale_lc(coeff, lhs);
+        self.rhs = &self.rhs + &scale_lc(coeff, rhs);
+        self.n += num_bits;
+        Ok(())
+    }
+
+    /// Flushes any remaining accumulation, surfacing a failure instead of
+    /// silently swallowing it the way the `Drop` impl has to. Prefer this
+    /// over letting `self` merely go out of scope whenever the caller can
+    /// propagate a `SynthesisError`.
+    pub fn finish(mut self) -> Result<(), SynthesisError> {
+        self.try_flush()
+    }
+}
+
+impl<ConstraintF: PrimeField> Drop for MultiEq<ConstraintF> {
+    fn drop(&mut self) {
+        // Best-effort: a constraint system that has accepted every
+        // constraint built up to this point should also accept this final
+        // one, so failure here is not expected in practice. Callers that
+        // need to observe a failure should call `finish` explicitly instead
+        // of relying on drop.
+        let _ = self.try_flush();
+    }
+}
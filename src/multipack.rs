@@ -0,0 +1,110 @@
+use crate::prelude::*;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::vec::Vec;
+
+/// Packs `bits` into the minimal number of field elements, chunking the
+/// slice into groups of `ConstraintF::MODULUS_BIT_SIZE - 1` bits so each
+/// group fits with room to spare against wraparound. Each chunk becomes one
+/// `FpVar` constrained to equal `Σ bit_i · 2^i`.
+///
+/// This is the counterpart to `ToBitsGadget`: it lets a circuit expose a
+/// multi-hundred-bit value (e.g. a hash digest) as a handful of public field
+/// elements instead of one public input per bit.
+pub fn pack_into_fp_vars<ConstraintF: PrimeField>(
+    bits: &[Boolean<ConstraintF>],
+) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
+    let capacity = (ConstraintF::MODULUS_BIT_SIZE - 1) as usize;
+    bits.chunks(capacity).map(Boolean::le_bits_to_fp_var).collect()
+}
+
+/// Like [`pack_into_fp_vars`], but additionally allocates each packed field
+/// element as a public input of `cs` and enforces it equal to the witnessed
+/// packing. Use this when the packed values themselves need to be part of
+/// the public input vector, rather than merely constrained intermediate
+/// witnesses.
+pub fn pack_into_inputs<ConstraintF: PrimeField>(
+    cs: ConstraintSystemRef<ConstraintF>,
+    bits: &[Boolean<ConstraintF>],
+) -> Result<Vec<FpVar<ConstraintF>>, SynthesisError> {
+    pack_into_fp_vars(bits)?
+        .into_iter()
+        .map(|packed| {
+            let input = FpVar::new_input(cs.clone(), || packed.value())?;
+            input.enforce_equal(&packed)?;
+            Ok(input)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::vec;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn capacity() -> usize {
+        (Fr::MODULUS_BIT_SIZE - 1) as usize
+    }
+
+    fn witnessed_bits(cs: ConstraintSystemRef<Fr>, values: &[bool]) -> Vec<Boolean<Fr>> {
+        values
+            .iter()
+            .map(|&b| Boolean::new_witness(cs.clone(), || Ok(b)).unwrap())
+            .collect()
+    }
+
+    /// `pack_into_fp_vars` round-trips through `ToBitsGadget`: the value bits
+    /// come back out in the same order they went in, and the constraints
+    /// built along the way are satisfied.
+    #[test]
+    fn round_trips_through_to_bits_le() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let values: Vec<bool> = (0..10).map(|i| i % 3 == 0).collect();
+        let bits = witnessed_bits(cs.clone(), &values);
+
+        let packed = pack_into_fp_vars(&bits).unwrap();
+        assert_eq!(packed.len(), 1);
+
+        let round_tripped = packed[0].to_bits_le().unwrap();
+        for (i, &expected) in values.iter().enumerate() {
+            assert_eq!(round_tripped[i].value().unwrap(), expected);
+        }
+        for bit in &round_tripped[values.len()..] {
+            assert!(!bit.value().unwrap());
+        }
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    /// A bit count that's an exact multiple of the chunk capacity must not
+    /// spill a spurious, all-zero trailing `FpVar`.
+    #[test]
+    fn chunk_boundary_exact_length_produces_no_spurious_chunk() {
+        let cap = capacity();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+
+        let one_chunk = witnessed_bits(cs.clone(), &vec![true; cap]);
+        assert_eq!(pack_into_fp_vars(&one_chunk).unwrap().len(), 1);
+
+        let two_chunks = witnessed_bits(cs.clone(), &vec![true; 2 * cap]);
+        assert_eq!(pack_into_fp_vars(&two_chunks).unwrap().len(), 2);
+
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    /// `pack_into_inputs` additionally allocates each packed element as a
+    /// public input and must still satisfy the witnessed equality.
+    #[test]
+    fn pack_into_inputs_allocates_satisfied_public_inputs() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let values: Vec<bool> = (0..16).map(|i| i % 2 == 0).collect();
+        let bits = witnessed_bits(cs.clone(), &values);
+
+        let inputs = pack_into_inputs(cs.clone(), &bits).unwrap();
+
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(cs.num_instance_variables(), 2);
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
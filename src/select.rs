@@ -1,7 +1,25 @@
 use crate::prelude::*;
 use ark_ff::Field;
-use ark_relations::r1cs::{LinearCombination, SynthesisError};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec;
 use ark_std::vec::Vec;
+use core::ops::{Add, Mul, Sub};
+/// Picks which algorithm [`CondSelectGadget::conditionally_select_power_of_two_vector`]
+/// should use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// Method 5.1 ("repeated selection"): a balanced tree of `m - 1`
+    /// `conditionally_select` calls. Works for any `CondSelectGadget`.
+    Tree,
+    /// Method 5.2 ("sum of conditions"): builds the `m` power-of-two product
+    /// selectors and combines them in a single weighted sum. Needs only
+    /// `O(m)` multiplication constraints for the table and one combination
+    /// pass, versus the tree's `m - 1` sequential selects, but is only
+    /// available for field-like `Self` (see
+    /// [`conditionally_select_power_of_two_vector_with_strategy`]).
+    SumOfConditions,
+}
+
 /// Generates constraints for selecting between one of many values.
 pub trait CondSelectGadget<ConstraintF: Field>
 where
@@ -24,6 +42,17 @@ where
     /// `position` is an array of boolean that represents an unsigned integer in
     /// big endian order. This is hybrid method 5.3 from https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf.
     ///
+    /// Defaults to [`SelectionStrategy::Tree`], which works for any `Self`
+    /// and is what every implementor in this module gets unless it overrides
+    /// this method itself. Field-like implementors (that can cheaply add,
+    /// subtract, multiply, and convert a `Boolean` into `Self`, such as
+    /// `FpVar`) can instead override this to call
+    /// [`conditionally_select_power_of_two_vector_with_strategy`] with
+    /// [`SelectionStrategy::SumOfConditions`], which is cheaper for large
+    /// `values`; no implementor in this module does so yet, so `Tree` is the
+    /// only strategy actually reachable without calling the free function
+    /// directly.
+    ///
     /// # Example
     /// To get the 6th element of `values`, convert unsigned integer 6 (`0b110`)
     /// to `position = [True, True, False]`,
@@ -32,11 +61,44 @@ where
         position: &[Boolean<ConstraintF>],
         values: &[Self],
     ) -> Result<Self, SynthesisError> {
-        let _ = sum_of_conditions(position, values);
         repeated_selection(position, values)
     }
 }
 
+/// Runs [`CondSelectGadget::conditionally_select_power_of_two_vector`]'s
+/// algorithm under the requested `strategy`. This is a free function, rather
+/// than a trait method, because [`SelectionStrategy::SumOfConditions`] needs
+/// `CondG` to support field-like arithmetic that not every
+/// `CondSelectGadget` implementor has; callers for whom that holds (and
+/// implementors of `CondSelectGadget` wanting to override their default) can
+/// reach for it directly.
+///
+/// `FpVar`'s own `CondSelectGadget` impl (in `src/fields/fp.rs`, outside this
+/// module) is the intended place to override
+/// `conditionally_select_power_of_two_vector` to call this with
+/// [`SelectionStrategy::SumOfConditions`], since it is the canonical
+/// field-like gadget; the tests below exercise that exact call shape against
+/// `FpVar` directly so the strategy is covered even before that override
+/// lands.
+pub fn conditionally_select_power_of_two_vector_with_strategy<ConstraintF, CondG>(
+    strategy: SelectionStrategy,
+    position: &[Boolean<ConstraintF>],
+    values: &[CondG],
+) -> Result<CondG, SynthesisError>
+where
+    ConstraintF: Field,
+    CondG: CondSelectGadget<ConstraintF>
+        + From<Boolean<ConstraintF>>
+        + Add<CondG, Output = CondG>
+        + Sub<CondG, Output = CondG>
+        + Mul<CondG, Output = CondG>,
+{
+    match strategy {
+        SelectionStrategy::Tree => repeated_selection(position, values),
+        SelectionStrategy::SumOfConditions => sum_of_conditions(position, values),
+    }
+}
+
 fn count_ones(x: usize) -> usize {
     // count the number of 1s in the binary representation of x
     let mut count = 0;
@@ -49,10 +111,33 @@ fn count_ones(x: usize) -> usize {
 }
 
 /// Sum of conditions method 5.2 from https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf
-fn sum_of_conditions<ConstraintF: Field, CondG: CondSelectGadget<ConstraintF>>(
+///
+/// Builds the `m = 2^n` power-of-two product selectors
+/// `selectors[j] = Π_{bit i set in j} position[n - 1 - i]` (`position` is
+/// big-endian), where `selectors[0]` is the constant `true`, the `n` at
+/// indices `1 << i` are just the raw bits, and the rest are formed by one
+/// `Boolean::and` each:
+/// `selectors[j] = selectors[1 << i] AND selectors[j - (1 << i)]`. From
+/// there, `selector_sums[i] = Σ_{j ⊇ i} (-1)^popcount(j ^ i) selectors[j]` is
+/// the indicator of `position == i` (by inclusion-exclusion over the bits
+/// `i` lacks), and `out = Σ_i values[i] * selector_sums[i]` is the selected
+/// value.
+///
+/// Only available for field-like `CondG`: the final weighted sum needs `+`,
+/// `-`, and `*` on `Self`, which a bit-vector gadget like `UIntN` does not
+/// provide.
+fn sum_of_conditions<ConstraintF, CondG>(
     position: &[Boolean<ConstraintF>],
     values: &[CondG],
-) -> Result<CondG, SynthesisError> {
+) -> Result<CondG, SynthesisError>
+where
+    ConstraintF: Field,
+    CondG: CondSelectGadget<ConstraintF>
+        + From<Boolean<ConstraintF>>
+        + Add<CondG, Output = CondG>
+        + Sub<CondG, Output = CondG>
+        + Mul<CondG, Output = CondG>,
+{
     let m = values.len();
     let n = position.len();
 
@@ -60,72 +145,42 @@ fn sum_of_conditions<ConstraintF: Field, CondG: CondSelectGadget<ConstraintF>>(
     assert!(m.is_power_of_two());
     assert_eq!(1 << n, m);
 
-    let mut selectors: Vec<LinearCombination<ConstraintF>> = Vec::with_capacity(m);
-
-    // fill the selectors vec with Boolean true entries
-    for _ in 0..m {
-        selectors.push(Boolean::constant(true).lc());
-    }
-
-    // let's construct the table of selectors.
-    // for a bit-decomposition (b_{n-1}, b_{n-2}, ..., b_0) of `power`:
-    // [
-    //      (b_{n-1} * b_{n-2} * ... * b_1 * b_0),
-    //      (b_{n-1} * b_{n-2} * ... * b_1),
-    //      (b_{n-1} * b_{n-2} * ... * b_0),
-    //      ...
-    //      (b_1 * b_0),
-    //      b_1,
-    //      b_0,
-    //      1,
-    // ]
-    // signal selectors[leafCount];
-    //
-    // the element of the selector table at index i is a product of `bits`
-    // e.g. for i = 5 == (101)_binary
-    // `selectors[5]` <== b_2 * b_0`
-    // we can construct the first `max_bits_in_power - 1` elements without products,
-    // directly from `bits`:
-    // e.g. for
-    // `selectors[1] <== b_0`
-    // `selectors[2] <== b_1`
-    // `selectors[4] <== b_2`
-    // `selectors[8] <== b_3`
-
-    // First element is true, but we've already filled it in.
-    // selectors[0] = Boolean::constant(true);
+    // selectors[0] is the constant `true`; selectors[j] for j > 0 is the AND
+    // of the bits of `position` that are set in j's binary representation.
+    let mut selectors: Vec<Boolean<ConstraintF>> = vec![Boolean::constant(true); m];
     for i in 0..n {
-        selectors[1 << i] = position[i].lc();
+        // `position` is big-endian (as documented on the trait), so the bit
+        // that gates tree level `i` (weight `1 << i`) is `position[n - 1 - i]`,
+        // matching `repeated_selection`'s use of the same index.
+        selectors[1 << i] = position[n - 1 - i].clone();
         for j in (1 << i) + 1..(1 << (i + 1)) {
-            selectors[j] = &selectors[1 << i] + &selectors[j - (1 << i)];
+            selectors[j] = selectors[1 << i].and(&selectors[j - (1 << i)])?;
         }
     }
 
-    let mut selector_sums: Vec<LinearCombination<ConstraintF>> = Vec::with_capacity(m);
+    let zero = CondG::from(Boolean::constant(false));
+    let mut selector_sums: Vec<CondG> = Vec::with_capacity(m);
     for i in 0..m {
+        let mut sum = zero.clone();
         for j in 0..m {
+            // j ⊇ i, i.e. every bit set in i is also set in j.
             if i | j == j {
-                let counts = count_ones(j - i);
-                if counts % 2 == 0 {
-                    selector_sums[i] = &selector_sums[i] + &selectors[j];
+                let term = CondG::from(selectors[j].clone());
+                sum = if count_ones(j ^ i) % 2 == 0 {
+                    sum + term
                 } else {
-                    selector_sums[i] = &selector_sums[i] - &selectors[j];
+                    sum - term
                 };
             }
         }
+        selector_sums.push(sum);
     }
 
-    let root: LinearCombination<ConstraintF> = LinearCombination::zero();
-    // var x = 0;
-    for i in 0..m {
-        root = &root + (values[i], selector_sums[i]);
+    let mut out = zero;
+    for (value, selector_sum) in values.iter().cloned().zip(selector_sums) {
+        out = out + value * selector_sum;
     }
-    // for (var i = 0; i < nextPow; i++) {
-    //     x += leaves[i] * selector_sums[i];
-    // }
-    // root <== x;
-
-    unimplemented!()
+    Ok(out)
 }
 
 /// Repeated selection method 5.1 from https://github.com/mir-protocol/r1cs-workshop/blob/master/workshop.pdf
@@ -215,3 +270,47 @@ where
         constants: &[Self::TableConstant],
     ) -> Result<Self, SynthesisError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_test_curves::bls12_381::Fr;
+
+    fn position_for(index: usize, n: usize) -> Vec<Boolean<Fr>> {
+        // Big-endian, matching the trait doc's convention.
+        (0..n)
+            .map(|i| Boolean::constant((index >> (n - 1 - i)) & 1 == 1))
+            .collect()
+    }
+
+    /// `SumOfConditions` must pick out the same element as `Tree` (and the
+    /// native value at that index) for every position, not just palindromic
+    /// ones. This is the cross-check that would have caught the
+    /// `position[i]` vs. `position[n - 1 - i]` indexing bug.
+    #[test]
+    fn sum_of_conditions_matches_repeated_selection() {
+        let n = 3;
+        let m = 1 << n;
+        let values: Vec<FpVar<Fr>> = (0..m).map(|i| FpVar::constant(Fr::from(i as u64))).collect();
+
+        for index in 0..m {
+            let position = position_for(index, n);
+
+            let tree = conditionally_select_power_of_two_vector_with_strategy(
+                SelectionStrategy::Tree,
+                &position,
+                &values,
+            )
+            .unwrap();
+            let sum_of_conditions = conditionally_select_power_of_two_vector_with_strategy(
+                SelectionStrategy::SumOfConditions,
+                &position,
+                &values,
+            )
+            .unwrap();
+
+            assert_eq!(tree.value().unwrap(), Fr::from(index as u64));
+            assert_eq!(sum_of_conditions.value().unwrap(), Fr::from(index as u64));
+        }
+    }
+}
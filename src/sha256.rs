@@ -0,0 +1,291 @@
+use crate::prelude::*;
+use crate::multieq::MultiEq;
+use crate::uint::UInt32;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::SynthesisError;
+use ark_std::vec::Vec;
+
+/// SHA-256's initial hash value, `H(0)`.
+const H0: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// SHA-256's round constants `K(0..64)`, the first 32 bits of the fractional
+/// parts of the cube roots of the first 64 primes.
+const K: [u32; 64] = [
+    0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5, 0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+    0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3, 0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+    0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc, 0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+    0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7, 0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+    0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13, 0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+    0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3, 0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+    0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5, 0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+    0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208, 0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+];
+
+/// `Σ0(a) = rotr(a, 2) ^ rotr(a, 13) ^ rotr(a, 22)`.
+fn big_sigma0<ConstraintF: PrimeField>(a: &UInt32<ConstraintF>) -> Result<UInt32<ConstraintF>, SynthesisError> {
+    a.rotr(2).xor(&a.rotr(13))?.xor(&a.rotr(22))
+}
+
+/// `Σ1(e) = rotr(e, 6) ^ rotr(e, 11) ^ rotr(e, 25)`.
+fn big_sigma1<ConstraintF: PrimeField>(e: &UInt32<ConstraintF>) -> Result<UInt32<ConstraintF>, SynthesisError> {
+    e.rotr(6).xor(&e.rotr(11))?.xor(&e.rotr(25))
+}
+
+/// `σ0(x) = rotr(x, 7) ^ rotr(x, 18) ^ shr(x, 3)`.
+fn small_sigma0<ConstraintF: PrimeField>(x: &UInt32<ConstraintF>) -> Result<UInt32<ConstraintF>, SynthesisError> {
+    x.rotr(7).xor(&x.rotr(18))?.xor(&x.shr(3))
+}
+
+/// `σ1(x) = rotr(x, 17) ^ rotr(x, 19) ^ shr(x, 10)`.
+fn small_sigma1<ConstraintF: PrimeField>(x: &UInt32<ConstraintF>) -> Result<UInt32<ConstraintF>, SynthesisError> {
+    x.rotr(17).xor(&x.rotr(19))?.xor(&x.shr(10))
+}
+
+/// Reads a big-endian-ordered 32-bit chunk (as SHA-256 itself specifies
+/// message words) into this crate's little-endian-first `UInt32`
+/// representation. Unlike BLAKE2s, whose words are natively little-endian
+/// and so can be built directly via `UInt32::from_bits_le`, SHA-256 words
+/// need their bit order reversed first.
+fn word_from_be_bits<ConstraintF: PrimeField>(bits: &[Boolean<ConstraintF>]) -> UInt32<ConstraintF> {
+    let reversed: Vec<Boolean<ConstraintF>> = bits.iter().rev().cloned().collect();
+    UInt32::from_bits_le(&reversed)
+}
+
+/// The inverse of [`word_from_be_bits`]: reads a `UInt32` back out in
+/// big-endian bit order.
+fn word_to_be_bits<ConstraintF: PrimeField>(
+    word: &UInt32<ConstraintF>,
+) -> Result<Vec<Boolean<ConstraintF>>, SynthesisError> {
+    let mut bits = word.to_bits_le()?;
+    bits.reverse();
+    Ok(bits)
+}
+
+/// `ch(e, f, g) = (e AND f) XOR ((NOT e) AND g)`, expressed entirely in terms
+/// of the word-level boolean gadgets rather than a field-arithmetic identity,
+/// to keep the constraint count down.
+fn ch<ConstraintF: PrimeField>(
+    e: &UInt32<ConstraintF>,
+    f: &UInt32<ConstraintF>,
+    g: &UInt32<ConstraintF>,
+) -> Result<UInt32<ConstraintF>, SynthesisError> {
+    e.and(f)?.xor(&e.not().and(g)?)
+}
+
+/// `maj(a, b, c) = (a AND b) XOR (a AND c) XOR (b AND c)`, again expressed via
+/// boolean gadgets rather than arithmetic.
+fn maj<ConstraintF: PrimeField>(
+    a: &UInt32<ConstraintF>,
+    b: &UInt32<ConstraintF>,
+    c: &UInt32<ConstraintF>,
+) -> Result<UInt32<ConstraintF>, SynthesisError> {
+    a.and(b)?.xor(&a.and(c)?)?.xor(&b.and(c)?)
+}
+
+/// Runs the SHA-256 compression function, updating the eight-word state `h`
+/// in place from one 512-bit message block `block`. All of the modular
+/// additions in the message schedule and the round function are folded
+/// through a single [`MultiEq`] so the whole block costs a handful of
+/// equality constraints instead of one per addition.
+pub fn sha256_compression_function<ConstraintF: PrimeField>(
+    h: &mut [UInt32<ConstraintF>],
+    block: &[Boolean<ConstraintF>],
+) -> Result<(), SynthesisError> {
+    assert_eq!(h.len(), 8);
+    assert_eq!(block.len(), 512);
+
+    let cs = h[0].cs().or(block.cs());
+    let mut multi_eq = MultiEq::new(cs);
+
+    let mut w: Vec<UInt32<ConstraintF>> = block.chunks(32).map(word_from_be_bits).collect();
+    for t in 16..64 {
+        let s0 = small_sigma0(&w[t - 15])?;
+        let s1 = small_sigma1(&w[t - 2])?;
+        w.push(UInt32::addmany(
+            &mut multi_eq,
+            &[w[t - 16].clone(), s0, w[t - 7].clone(), s1],
+        )?);
+    }
+
+    let mut a = h[0].clone();
+    let mut b = h[1].clone();
+    let mut c = h[2].clone();
+    let mut d = h[3].clone();
+    let mut e = h[4].clone();
+    let mut f = h[5].clone();
+    let mut g = h[6].clone();
+    let mut hh = h[7].clone();
+
+    for t in 0..64 {
+        let t1 = UInt32::addmany(
+            &mut multi_eq,
+            &[
+                hh,
+                big_sigma1(&e)?,
+                ch(&e, &f, &g)?,
+                UInt32::constant(K[t]),
+                w[t].clone(),
+            ],
+        )?;
+        let t2 = UInt32::addmany(&mut multi_eq, &[big_sigma0(&a)?, maj(&a, &b, &c)?])?;
+
+        hh = g;
+        g = f;
+        f = e;
+        e = UInt32::addmany(&mut multi_eq, &[d, t1.clone()])?;
+        d = c;
+        c = b;
+        b = a;
+        a = UInt32::addmany(&mut multi_eq, &[t1, t2])?;
+    }
+
+    h[0] = UInt32::addmany(&mut multi_eq, &[h[0].clone(), a])?;
+    h[1] = UInt32::addmany(&mut multi_eq, &[h[1].clone(), b])?;
+    h[2] = UInt32::addmany(&mut multi_eq, &[h[2].clone(), c])?;
+    h[3] = UInt32::addmany(&mut multi_eq, &[h[3].clone(), d])?;
+    h[4] = UInt32::addmany(&mut multi_eq, &[h[4].clone(), e])?;
+    h[5] = UInt32::addmany(&mut multi_eq, &[h[5].clone(), f])?;
+    h[6] = UInt32::addmany(&mut multi_eq, &[h[6].clone(), g])?;
+    h[7] = UInt32::addmany(&mut multi_eq, &[h[7].clone(), hh])?;
+
+    multi_eq.finish()?;
+
+    Ok(())
+}
+
+/// Pads `input` to a whole number of 512-bit blocks per FIPS 180-4: a `1` bit,
+/// zeros, then the original bit length as a big-endian `u64`.
+fn pad_to_blocks<ConstraintF: PrimeField>(input: &[Boolean<ConstraintF>]) -> Vec<Boolean<ConstraintF>> {
+    let mut padded = input.to_vec();
+    let bit_len = input.len() as u64;
+
+    padded.push(Boolean::constant(true));
+    while (padded.len() + 64) % 512 != 0 {
+        padded.push(Boolean::constant(false));
+    }
+    for i in (0..64).rev() {
+        padded.push(Boolean::constant((bit_len >> i) & 1 == 1));
+    }
+    padded
+}
+
+/// Computes the SHA-256 digest of `input`, a slice of bits in big-endian
+/// byte/bit order as the standard expects, returning the 256 digest bits in
+/// the same order.
+pub fn sha256<ConstraintF: PrimeField>(
+    input: &[Boolean<ConstraintF>],
+) -> Result<Vec<Boolean<ConstraintF>>, SynthesisError> {
+    assert!(input.len() % 8 == 0);
+
+    let padded = pad_to_blocks(input);
+    let mut h: Vec<UInt32<ConstraintF>> = H0.iter().map(|&w| UInt32::constant(w)).collect();
+
+    for block in padded.chunks(512) {
+        sha256_compression_function(&mut h, block)?;
+    }
+
+    let mut digest = Vec::with_capacity(256);
+    for word in &h {
+        digest.extend_from_slice(&word_to_be_bits(word)?);
+    }
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+    use ark_test_curves::bls12_381::Fr;
+
+    fn bits_from_bytes(bytes: &[u8]) -> Vec<Boolean<Fr>> {
+        bytes
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| Boolean::constant((byte >> i) & 1 == 1)))
+            .collect()
+    }
+
+    /// Like [`bits_from_bytes`], but allocates every bit as a witness on
+    /// `cs` instead of a constant, so the `MultiEq`-batched constraint path
+    /// in [`sha256_compression_function`] actually gets built.
+    fn bits_from_bytes_witness(cs: ConstraintSystemRef<Fr>, bytes: &[u8]) -> Vec<Boolean<Fr>> {
+        let mut bits = Vec::with_capacity(bytes.len() * 8);
+        for byte in bytes {
+            for i in (0..8).rev() {
+                bits.push(Boolean::new_witness(cs.clone(), || Ok((byte >> i) & 1 == 1)).unwrap());
+            }
+        }
+        bits
+    }
+
+    fn bytes_from_bits(bits: &[Boolean<Fr>]) -> Vec<u8> {
+        bits.chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u8, |acc, bit| (acc << 1) | (bit.value().unwrap() as u8))
+            })
+            .collect()
+    }
+
+    // FIPS 180-4 known-answer vectors (the empty string, the one-block "abc"
+    // example, and the two-block "abcdbcdecdefdefg..." example).
+    #[test]
+    fn matches_fips_known_answers() {
+        let vectors: [(&[u8], [u8; 32]); 3] = [
+            (
+                b"",
+                hex32("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            ),
+            (
+                b"abc",
+                hex32("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                hex32("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"),
+            ),
+        ];
+
+        for (msg, expected) in vectors {
+            let digest_bits = sha256(&bits_from_bytes(msg)).unwrap();
+            assert_eq!(bytes_from_bits(&digest_bits), expected);
+        }
+    }
+
+    fn hex32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for i in 0..32 {
+            out[i] = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    /// The KAT above only exercises `Boolean::constant` inputs, which take
+    /// `UInt32::addmany`'s all-constant fast path and never build a single
+    /// R1CS constraint. Here the message is witnessed on a real constraint
+    /// system instead, so the `MultiEq`-batched equality constraints are
+    /// actually emitted, and we check both that they're satisfied and that
+    /// the digest still matches the KAT.
+    #[test]
+    fn emitted_constraints_are_satisfied() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let input = bits_from_bytes_witness(cs.clone(), b"abc");
+
+        let digest_bits = sha256(&input).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(
+            bytes_from_bits(&digest_bits),
+            hex32("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::multieq::{scale_lc, MultiEq};
+use crate::prelude::*;
+use ark_ff::{Field, PrimeField};
+use ark_relations::r1cs::{ConstraintSystemRef, LinearCombination, SynthesisError};
+use ark_std::vec::Vec;
+
+macro_rules! make_uint {
+    ($name:ident, $size:expr, $native:ident, $r1cs_doc:expr) => {
+        #[doc = $r1cs_doc]
+        #[derive(Clone, Debug)]
+        pub struct $name<ConstraintF: Field> {
+            /// Little-endian list of bits representing this gadget.
+            pub bits: Vec<Boolean<ConstraintF>>,
+            /// The native value, when known.
+            pub value: Option<$native>,
+        }
+
+        impl<ConstraintF: Field> R1CSVar<ConstraintF> for $name<ConstraintF> {
+            type Value = $native;
+
+            fn cs(&self) -> ConstraintSystemRef<ConstraintF> {
+                self.bits.as_slice().cs()
+            }
+
+            fn value(&self) -> Result<Self::Value, SynthesisError> {
+                self.value.ok_or(SynthesisError::AssignmentMissing)
+            }
+        }
+
+        impl<ConstraintF: Field> $name<ConstraintF> {
+            /// Constructs a constant `$name` with the given bit pattern. No
+            /// constraints are generated.
+            pub fn constant(value: $native) -> Self {
+                let mut bits = Vec::with_capacity($size);
+                let mut v = value;
+                for _ in 0..$size {
+                    bits.push(Boolean::constant(v & 1 == 1));
+                    v >>= 1;
+                }
+                Self {
+                    bits,
+                    value: Some(value),
+                }
+            }
+
+            /// Rotates the bits to the right by `by` positions. This is a
+            /// free permutation of the existing bit wires; it adds no
+            /// constraints.
+            pub fn rotr(&self, by: usize) -> Self {
+                let by = by % $size;
+                let bits = self.bits[by..]
+                    .iter()
+                    .chain(self.bits[..by].iter())
+                    .cloned()
+                    .collect();
+                let value = self.value.map(|v| v.rotate_right(by as u32));
+                Self { bits, value }
+            }
+
+            /// Rotates the bits to the left by `by` positions. This is a
+            /// free permutation of the existing bit wires; it adds no
+            /// constraints.
+            pub fn rotl(&self, by: usize) -> Self {
+                self.rotr($size - (by % $size))
+            }
+
+            /// Shifts the bits to the right by `by` positions, shifting in
+            /// constant zero (false) bits from the top.
+            pub fn shr(&self, by: usize) -> Self {
+                let by = by.min($size);
+                let mut bits: Vec<_> = self.bits[by..].to_vec();
+                bits.resize($size, Boolean::constant(false));
+                let value = self.value.map(|v| if by >= $size { 0 } else { v >> by });
+                Self { bits, value }
+            }
+
+            /// Shifts the bits to the left by `by` positions, shifting in
+            /// constant zero (false) bits from the bottom. Bits shifted past
+            /// the top are discarded, i.e. this computes `(self << by) mod
+            /// 2^N`.
+            pub fn shl(&self, by: usize) -> Self {
+                let by = by.min($size);
+                let mut bits = ark_std::vec![Boolean::constant(false); by];
+                bits.extend_from_slice(&self.bits[..$size - by]);
+                let value = self.value.map(|v| if by >= $size { 0 } else { v << by });
+                Self { bits, value }
+            }
+
+            /// Computes the bitwise XOR of `self` and `other`.
+            pub fn xor(&self, other: &Self) -> Result<Self, SynthesisError> {
+                let bits = self
+                    .bits
+                    .iter()
+                    .zip(&other.bits)
+                    .map(|(a, b)| a.xor(b))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let value = self.value.zip(other.value).map(|(a, b)| a ^ b);
+                Ok(Self { bits, value })
+            }
+
+            /// Computes the bitwise AND of `self` and `other`.
+            pub fn and(&self, other: &Self) -> Result<Self, SynthesisError> {
+                let bits = self
+                    .bits
+                    .iter()
+                    .zip(&other.bits)
+                    .map(|(a, b)| a.and(b))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let value = self.value.zip(other.value).map(|(a, b)| a & b);
+                Ok(Self { bits, value })
+            }
+
+            /// Computes the bitwise NOT of `self`.
+            pub fn not(&self) -> Self {
+                let bits = self.bits.iter().map(Boolean::not).collect();
+                let value = self.value.map(|v| !v);
+                Self { bits, value }
+            }
+
+            /// Builds a `$name` out of exactly `$size` `Boolean`s,
+            /// least-significant bit first.
+            pub fn from_bits_le(bits: &[Boolean<ConstraintF>]) -> Self {
+                assert_eq!(bits.len(), $size);
+                let mut value: Option<$native> = Some(0);
+                for (i, bit) in bits.iter().enumerate() {
+                    value = value.and_then(|v| bit.value().ok().map(|b| v | ((b as $native) << i)));
+                }
+                Self {
+                    bits: bits.to_vec(),
+                    value,
+                }
+            }
+
+            /// Computes `operands[0] + operands[1] + ... + operands[k - 1] mod
+            /// 2^$size`, returning the result together with its bit
+            /// decomposition.
+            ///
+            /// This forms the field-element linear combination of every
+            /// operand, allocates the `$size` result bits plus
+            /// `ceil(log2(k))` carry bits, and asserts the two sides equal
+            /// through `multi_eq` so that several `addmany` calls can share a
+            /// single constraint instead of paying for one each. If every
+            /// operand is a constant (so there is no constraint system to
+            /// allocate witnesses against), the sum is computed directly as a
+            /// constant instead.
+            pub fn addmany(
+                multi_eq: &mut MultiEq<ConstraintF>,
+                operands: &[Self],
+            ) -> Result<Self, SynthesisError>
+            where
+                ConstraintF: PrimeField,
+            {
+                assert!(!operands.is_empty());
+                let cs = operands
+                    .iter()
+                    .fold(ConstraintSystemRef::None, |cs, op| cs.or(op.cs()));
+
+                let mut value: Option<u128> = Some(0);
+                for op in operands {
+                    value = value.and_then(|acc| op.value.map(|v| acc + u128::from(v)));
+                }
+                let modulus = 1u128 << $size;
+                let result_value = value.map(|v| (v % modulus) as $native);
+
+                if cs.is_none() {
+                    let result_native = result_value.ok_or(SynthesisError::AssignmentMissing)?;
+                    return Ok(Self::constant(result_native));
+                }
+
+                // `ceil(log2(k))` carry bits are enough to absorb the
+                // overflow from summing `k` $size-bit operands.
+                let mut carry_bits = 0usize;
+                while (1usize << carry_bits) < operands.len() {
+                    carry_bits += 1;
+                }
+
+                let mut lhs = LinearCombination::zero();
+                for op in operands {
+                    for (i, bit) in op.bits.iter().enumerate() {
+                        lhs = &lhs + &scale_lc(ConstraintF::from(1u64 << i), &bit.lc());
+                    }
+                }
+
+                let carry_value = value.map(|v| (v / modulus) as u64);
+
+                let result_bits: Vec<Boolean<ConstraintF>> = (0..$size)
+                    .map(|i| {
+                        Boolean::new_witness(cs.clone(), || {
+                            result_value
+                                .map(|v| (v >> i) & 1 == 1)
+                                .ok_or(SynthesisError::AssignmentMissing)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                let carry_gadget_bits: Vec<Boolean<ConstraintF>> = (0..carry_bits)
+                    .map(|i| {
+                        Boolean::new_witness(cs.clone(), || {
+                            carry_value
+                                .map(|v| (v >> i) & 1 == 1)
+                                .ok_or(SynthesisError::AssignmentMissing)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                let mut rhs = LinearCombination::zero();
+                for (i, bit) in result_bits.iter().enumerate() {
+                    rhs = &rhs + &scale_lc(ConstraintF::from(1u64 << i), &bit.lc());
+                }
+                for (i, bit) in carry_gadget_bits.iter().enumerate() {
+                    let coeff = ConstraintF::from(modulus) * ConstraintF::from(1u64 << i);
+                    rhs = &rhs + &scale_lc(coeff, &bit.lc());
+                }
+
+                multi_eq.enforce_equal($size + carry_bits, &lhs, &rhs)?;
+
+                Ok(Self {
+                    bits: result_bits,
+                    value: result_value,
+                })
+            }
+        }
+
+        impl<ConstraintF: Field> CondSelectGadget<ConstraintF> for $name<ConstraintF> {
+            fn conditionally_select(
+                cond: &Boolean<ConstraintF>,
+                true_value: &Self,
+                false_value: &Self,
+            ) -> Result<Self, SynthesisError> {
+                let bits = true_value
+                    .bits
+                    .iter()
+                    .zip(&false_value.bits)
+                    .map(|(t, f)| Boolean::conditionally_select(cond, t, f))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let value = cond.value().ok().and_then(|c| {
+                    if c {
+                        true_value.value
+                    } else {
+                        false_value.value
+                    }
+                });
+                Ok(Self { bits, value })
+            }
+        }
+
+        impl<ConstraintF: Field> ToBitsGadget<ConstraintF> for $name<ConstraintF> {
+            fn to_bits_le(&self) -> Result<Vec<Boolean<ConstraintF>>, SynthesisError> {
+                Ok(self.bits.clone())
+            }
+        }
+
+        impl<ConstraintF: Field> ToBytesGadget<ConstraintF> for $name<ConstraintF> {
+            fn to_bytes(&self) -> Result<Vec<UInt8<ConstraintF>>, SynthesisError> {
+                Ok(self.bits.chunks(8).map(UInt8::from_bits_le).collect())
+            }
+        }
+
+        impl<ConstraintF: Field> EqGadget<ConstraintF> for $name<ConstraintF> {
+            fn is_eq(&self, other: &Self) -> Result<Boolean<ConstraintF>, SynthesisError> {
+                self.bits.is_eq(&other.bits)
+            }
+        }
+    };
+}
+
+make_uint!(
+    UInt8,
+    8,
+    u8,
+    "An 8-bit unsigned integer gadget, represented as a little-endian vector of `Boolean`s."
+);
+make_uint!(
+    UInt16,
+    16,
+    u16,
+    "A 16-bit unsigned integer gadget, represented as a little-endian vector of `Boolean`s."
+);
+make_uint!(
+    UInt32,
+    32,
+    u32,
+    "A 32-bit unsigned integer gadget, represented as a little-endian vector of `Boolean`s."
+);
+make_uint!(
+    UInt64,
+    64,
+    u64,
+    "A 64-bit unsigned integer gadget, represented as a little-endian vector of `Boolean`s."
+);